@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+/// Response from the device-authorization endpoint.
+#[derive(Debug, Deserialize)]
+struct DeviceAuthResponse {
+    verification_url: String,
+    user_code: String,
+    device_code: String,
+    #[serde(default = "default_poll_interval")]
+    poll_interval: u64,
+}
+
+fn default_poll_interval() -> u64 {
+    5
+}
+
+/// Response from the token endpoint, both for the device-flow poll and for
+/// a refresh-token exchange.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    error: Option<String>,
+}
+
+/// What we persist to disk between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TokenCache {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: DateTime<Utc>,
+}
+
+fn cache_path(space_domain: &str) -> PathBuf {
+    let mut path = dirs::cache_dir().unwrap_or_else(std::env::temp_dir);
+    path.push("space-copyright-form");
+    fs::create_dir_all(&path).ok();
+    path.push(format!("{space_domain}.json"));
+    path
+}
+
+fn load_cache(path: &Path) -> Option<TokenCache> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn save_cache(path: &Path, cache: &TokenCache) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+/// Return a valid `SPACE_TOKEN`, running the OAuth device-authorization flow
+/// interactively (and caching the result) when no cached token is usable.
+pub(crate) fn get_access_token(
+    space_domain: &str,
+    client_id: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let client = Client::new();
+    let path = cache_path(space_domain);
+
+    if let Some(cache) = load_cache(&path) {
+        if cache.expires_at > Utc::now() {
+            return Ok(cache.access_token);
+        }
+        if let Some(refresh_token) = &cache.refresh_token {
+            if let Ok(cache) = refresh(&client, space_domain, client_id, refresh_token) {
+                save_cache(&path, &cache)?;
+                return Ok(cache.access_token);
+            }
+        }
+    }
+
+    let cache = device_flow(&client, space_domain, client_id)?;
+    save_cache(&path, &cache)?;
+    Ok(cache.access_token)
+}
+
+fn refresh(
+    client: &Client,
+    space_domain: &str,
+    client_id: &str,
+    refresh_token: &str,
+) -> Result<TokenCache, Box<dyn std::error::Error>> {
+    let response: TokenResponse = client
+        .post(format!("https://{space_domain}/oauth/token"))
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", client_id),
+            ("refresh_token", refresh_token),
+        ])
+        .send()?
+        .json()?;
+
+    to_cache(response)
+}
+
+fn device_flow(
+    client: &Client,
+    space_domain: &str,
+    client_id: &str,
+) -> Result<TokenCache, Box<dyn std::error::Error>> {
+    let auth: DeviceAuthResponse = client
+        .post(format!("https://{space_domain}/oauth/device/authorize"))
+        .form(&[("client_id", client_id)])
+        .send()?
+        .json()?;
+
+    println!(
+        "To authorize this tool, open {} and enter code: {}",
+        auth.verification_url, auth.user_code
+    );
+
+    loop {
+        sleep(Duration::from_secs(auth.poll_interval));
+
+        let response: TokenResponse = client
+            .post(format!("https://{space_domain}/oauth/token"))
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("client_id", client_id),
+                ("device_code", &auth.device_code),
+            ])
+            .send()?
+            .json()?;
+
+        match response.error.as_deref() {
+            Some("authorization_pending") => continue,
+            Some(other) => return Err(format!("device authorization failed: {other}").into()),
+            None => return to_cache(response),
+        }
+    }
+}
+
+fn to_cache(response: TokenResponse) -> Result<TokenCache, Box<dyn std::error::Error>> {
+    let access_token = response
+        .access_token
+        .ok_or("token endpoint response was missing access_token")?;
+    let expires_at = Utc::now()
+        + chrono::Duration::seconds(response.expires_in.unwrap_or(3600));
+    Ok(TokenCache {
+        access_token,
+        refresh_token: response.refresh_token,
+        expires_at,
+    })
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+/// Errors surfaced by this tool, in place of the `expect`/`Box<dyn Error>`
+/// panics it used to rely on.
+#[derive(Debug)]
+pub(crate) enum Error {
+    MissingEnv(String),
+    Http { status: u16, body: String },
+    Api { code: String, message: String },
+    Parse(String),
+    Config(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingEnv(name) => {
+                write!(f, "missing required environment variable `{name}`")
+            }
+            Error::Http { status, body } => {
+                write!(f, "Space API request failed with HTTP {status}: {body}")
+            }
+            Error::Api { code, message } => write!(f, "Space API error `{code}`: {message}"),
+            Error::Parse(msg) => write!(f, "failed to parse Space API response: {msg}"),
+            Error::Config(msg) => write!(f, "invalid configuration: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Parse(err.to_string())
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http {
+            status: err.status().map(|s| s.as_u16()).unwrap_or(0),
+            body: err.to_string(),
+        }
+    }
+}
+
+/// The two error envelope shapes Space's API is known to return.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ApiErrorEnvelope {
+    OAuthStyle {
+        error: String,
+        error_description: Option<String>,
+    },
+    CodeStyle {
+        code: String,
+        message: String,
+    },
+}
+
+/// Turn a non-success HTTP response body into a structured [`Error`],
+/// falling back to [`Error::Http`] when the body isn't a known error shape.
+pub(crate) fn decode_api_error(status: u16, body: &str) -> Error {
+    match serde_json::from_str::<ApiErrorEnvelope>(body) {
+        Ok(ApiErrorEnvelope::OAuthStyle {
+            error,
+            error_description,
+        }) => Error::Api {
+            code: error,
+            message: error_description.unwrap_or_default(),
+        },
+        Ok(ApiErrorEnvelope::CodeStyle { code, message }) => Error::Api { code, message },
+        Err(_) => Error::Http {
+            status,
+            body: body.to_string(),
+        },
+    }
+}
+
+/// Read a required environment variable, turning the absence into an
+/// [`Error::MissingEnv`] instead of panicking.
+pub(crate) fn require_env(name: &str) -> Result<String, Error> {
+    std::env::var(name).map_err(|_| Error::MissingEnv(name.to_string()))
+}
@@ -0,0 +1,512 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{Review, ReviewState};
+
+/// Fields of [`Review`] that the filter DSL can compare against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Field {
+    State,
+    Title,
+    Project,
+    Number,
+    CreatedAt,
+}
+
+impl Field {
+    fn parse(name: &str) -> Result<Field, FilterError> {
+        match name {
+            "state" => Ok(Field::State),
+            "title" => Ok(Field::Title),
+            "project" => Ok(Field::Project),
+            "number" => Ok(Field::Number),
+            "created_at" => Ok(Field::CreatedAt),
+            other => Err(FilterError::Parse(format!("unknown field `{other}`"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    In,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    Number(i64),
+    Text(String),
+    Date(NaiveDate),
+    List(Vec<Value>),
+}
+
+/// AST produced by [`parse`].
+#[derive(Debug, Clone)]
+pub(crate) enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Comparison { field: Field, op: Op, value: Value },
+}
+
+#[derive(Debug)]
+pub(crate) enum FilterError {
+    Parse(String),
+    Eval(String),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::Parse(msg) => write!(f, "invalid filter expression: {msg}"),
+            FilterError::Eval(msg) => write!(f, "could not evaluate filter: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Parse a filter expression such as `state = Closed AND project = BAZEL`
+/// into an [`Expr`] tree.
+pub(crate) fn parse(input: &str) -> Result<Expr, FilterError> {
+    let tokens = lex(input)?;
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(FilterError::Parse(format!(
+            "unexpected trailing token `{:?}`",
+            parser.tokens[parser.pos]
+        )));
+    }
+    Ok(expr)
+}
+
+/// Evaluate `expr` against a single review.
+pub(crate) fn eval(expr: &Expr, review: &Review) -> Result<bool, FilterError> {
+    match expr {
+        Expr::And(lhs, rhs) => Ok(eval(lhs, review)? && eval(rhs, review)?),
+        Expr::Or(lhs, rhs) => Ok(eval(lhs, review)? || eval(rhs, review)?),
+        Expr::Not(inner) => Ok(!eval(inner, review)?),
+        Expr::Comparison { field, op, value } => eval_comparison(*field, *op, value, review),
+    }
+}
+
+fn eval_comparison(field: Field, op: Op, value: &Value, review: &Review) -> Result<bool, FilterError> {
+    match field {
+        Field::State => {
+            let state = match review.state {
+                ReviewState::Opened => "Opened",
+                ReviewState::Closed => "Closed",
+                ReviewState::Deleted => "Deleted",
+            };
+            let Value::Text(expected) = value else {
+                return Err(FilterError::Eval("`state` expects a bare word or string".into()));
+            };
+            compare_eq(op, state, expected)
+        }
+        Field::Title => {
+            let Value::Text(expected) = value else {
+                return Err(FilterError::Eval("`title` expects a string".into()));
+            };
+            match op {
+                Op::Contains => Ok(review.title.contains(expected.as_str())),
+                Op::Eq => Ok(review.title == *expected),
+                Op::Ne => Ok(review.title != *expected),
+                _ => Err(FilterError::Eval("`title` only supports =, != and ~".into())),
+            }
+        }
+        Field::Project => {
+            let Value::Text(expected) = value else {
+                return Err(FilterError::Eval("`project` expects a bare word or string".into()));
+            };
+            compare_eq(op, &review.project.key, expected)
+        }
+        Field::Number => match (op, value) {
+            (Op::In, Value::List(items)) => Ok(items
+                .iter()
+                .any(|item| matches!(item, Value::Number(n) if *n == review.number as i64))),
+            (_, Value::Number(expected)) => compare_ord(op, review.number as i64, *expected),
+            _ => Err(FilterError::Eval("`number` expects a number or a list with IN".into())),
+        },
+        Field::CreatedAt => {
+            let Value::Date(expected) = value else {
+                return Err(FilterError::Eval("`created_at` expects an ISO date".into()));
+            };
+            compare_ord(op, review.created_at.date_naive(), *expected)
+        }
+    }
+}
+
+fn compare_eq(op: Op, actual: &str, expected: &str) -> Result<bool, FilterError> {
+    match op {
+        Op::Eq => Ok(actual == expected),
+        Op::Ne => Ok(actual != expected),
+        Op::Contains => Ok(actual.contains(expected)),
+        _ => Err(FilterError::Eval(format!("`{op:?}` is not supported for text fields"))),
+    }
+}
+
+fn compare_ord<T: PartialOrd>(op: Op, actual: T, expected: T) -> Result<bool, FilterError> {
+    match op {
+        Op::Eq => Ok(actual == expected),
+        Op::Ne => Ok(actual != expected),
+        Op::Gt => Ok(actual > expected),
+        Op::Ge => Ok(actual >= expected),
+        Op::Lt => Ok(actual < expected),
+        Op::Le => Ok(actual <= expected),
+        _ => Err(FilterError::Eval(format!("`{op:?}` is not supported for this field"))),
+    }
+}
+
+// --- lexer -----------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Number(i64),
+    Date(NaiveDate),
+    Op(Op),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    And,
+    Or,
+    Not,
+}
+
+fn lex(input: &str) -> Result<Vec<Token>, FilterError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '~' => {
+                tokens.push(Token::Op(Op::Contains));
+                i += 1;
+            }
+            '=' => {
+                tokens.push(Token::Op(Op::Eq));
+                i += 1;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Ne));
+                i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Ge));
+                i += 2;
+            }
+            '>' => {
+                tokens.push(Token::Op(Op::Gt));
+                i += 1;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Le));
+                i += 2;
+            }
+            '<' => {
+                tokens.push(Token::Op(Op::Lt));
+                i += 1;
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                if i == chars.len() {
+                    return Err(FilterError::Parse("unterminated string literal".into()));
+                }
+                i += 1; // closing quote
+                tokens.push(Token::String(s));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+                    tokens.push(Token::Date(date));
+                } else {
+                    tokens.push(Token::Number(text.parse().map_err(|_| {
+                        FilterError::Parse(format!("invalid number `{text}`"))
+                    })?));
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "IN" => Token::Op(Op::In),
+                    _ => Token::Ident(word),
+                });
+            }
+            other => {
+                return Err(FilterError::Parse(format!("unexpected character `{other}`")));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// --- recursive-descent parser -----------------------------------------------
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, FilterError> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.bump();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, FilterError> {
+        let mut lhs = self.parse_unary()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.bump();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, FilterError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.bump();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if matches!(self.peek(), Some(Token::LParen)) {
+            self.bump();
+            let inner = self.parse_or()?;
+            match self.bump() {
+                Some(Token::RParen) => return Ok(inner),
+                _ => return Err(FilterError::Parse("expected closing `)`".into())),
+            }
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, FilterError> {
+        let field = match self.bump() {
+            Some(Token::Ident(name)) => Field::parse(name)?,
+            other => {
+                return Err(FilterError::Parse(format!(
+                    "expected a field name, got `{other:?}`"
+                )))
+            }
+        };
+        let op = match self.bump() {
+            Some(Token::Op(op)) => *op,
+            other => {
+                return Err(FilterError::Parse(format!(
+                    "expected a comparison operator, got `{other:?}`"
+                )))
+            }
+        };
+        let value = self.parse_value()?;
+        Ok(Expr::Comparison { field, op, value })
+    }
+
+    fn parse_value(&mut self) -> Result<Value, FilterError> {
+        match self.bump() {
+            Some(Token::String(s)) => Ok(parse_string_value(s)),
+            Some(Token::Ident(word)) => Ok(Value::Text(word.clone())),
+            Some(Token::Number(n)) => Ok(Value::Number(*n)),
+            Some(Token::Date(d)) => Ok(Value::Date(*d)),
+            Some(Token::LBracket) => {
+                let mut items = Vec::new();
+                loop {
+                    if matches!(self.peek(), Some(Token::RBracket)) {
+                        self.bump();
+                        break;
+                    }
+                    items.push(self.parse_value()?);
+                    match self.bump() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RBracket) => break,
+                        other => {
+                            return Err(FilterError::Parse(format!(
+                                "expected `,` or `]` in list, got `{other:?}`"
+                            )))
+                        }
+                    }
+                }
+                Ok(Value::List(items))
+            }
+            other => Err(FilterError::Parse(format!("expected a value, got `{other:?}`"))),
+        }
+    }
+}
+
+fn parse_string_value(s: &str) -> Value {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(Value::Date)
+        .unwrap_or_else(|_| Value::Text(s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::Project;
+
+    fn review(number: i32, title: &str, state: ReviewState, created_at: &str) -> Review {
+        Review {
+            number,
+            title: title.to_string(),
+            state,
+            project: Project {
+                key: "BAZEL".to_string(),
+            },
+            created_at: {
+                let date = NaiveDate::parse_from_str(created_at, "%Y-%m-%d").unwrap();
+                Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+            },
+        }
+    }
+
+    fn matches(expr: &str, review: &Review) -> bool {
+        eval(&parse(expr).unwrap(), review).unwrap()
+    }
+
+    #[test]
+    fn parses_bare_iso_dates() {
+        let expr = parse("created_at >= 2024-03-10").unwrap();
+        assert!(matches!(
+            expr,
+            Expr::Comparison {
+                value: Value::Date(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn quoted_and_bare_dates_parse_to_the_same_value() {
+        let bare = parse("created_at >= 2024-03-10").unwrap();
+        let quoted = parse(r#"created_at >= "2024-03-10""#).unwrap();
+        let Expr::Comparison { value: Value::Date(a), .. } = bare else {
+            panic!("expected the bare-date expression to parse to a date comparison");
+        };
+        let Expr::Comparison { value: Value::Date(b), .. } = quoted else {
+            panic!("expected the quoted-date expression to parse to a date comparison");
+        };
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn comparison_operators() {
+        let r = review(10, "Fix the bug", ReviewState::Opened, "2024-03-10");
+        assert!(matches("number = 10", &r));
+        assert!(matches("number != 11", &r));
+        assert!(matches("number > 9", &r));
+        assert!(matches("number >= 10", &r));
+        assert!(matches("number < 11", &r));
+        assert!(matches("number <= 10", &r));
+        assert!(matches("title ~ bug", &r));
+        assert!(matches("state = Opened", &r));
+        assert!(!matches("state = Closed", &r));
+    }
+
+    #[test]
+    fn and_or_not_precedence() {
+        let r = review(10, "Fix the bug", ReviewState::Opened, "2024-03-10");
+        // AND binds tighter than OR: this reads as `number = 1 OR (number = 10 AND state = Opened)`.
+        assert!(matches("number = 1 OR number = 10 AND state = Opened", &r));
+        assert!(!matches("NOT number = 10", &r));
+        assert!(matches("NOT number = 1 AND state = Opened", &r));
+        assert!(matches("(number = 1 OR number = 10) AND state = Opened", &r));
+    }
+
+    #[test]
+    fn in_list_of_numbers() {
+        let r = review(10, "Fix the bug", ReviewState::Opened, "2024-03-10");
+        assert!(matches("number IN [1, 10, 20]", &r));
+        assert!(!matches("number IN [1, 2, 20]", &r));
+    }
+
+    #[test]
+    fn created_at_ordering() {
+        let r = review(10, "Fix the bug", ReviewState::Opened, "2024-03-10");
+        assert!(matches("created_at >= 2024-03-01", &r));
+        assert!(matches("created_at < 2024-04-01", &r));
+        assert!(!matches("created_at > 2024-03-10", &r));
+    }
+
+    #[test]
+    fn type_mismatch_is_a_runtime_eval_error() {
+        let r = review(10, "Fix the bug", ReviewState::Opened, "2024-03-10");
+        let expr = parse("number = foo").unwrap();
+        assert!(eval(&expr, &r).is_err());
+    }
+
+    #[test]
+    fn unknown_field_is_a_parse_error() {
+        assert!(parse("bogus = 1").is_err());
+    }
+
+    #[test]
+    fn unterminated_string_is_a_parse_error() {
+        assert!(parse(r#"title = "unterminated"#).is_err());
+    }
+}
@@ -1,14 +1,27 @@
 use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use clap::Parser;
 use reqwest::blocking::Client;
 use reqwest::header::{ACCEPT, AUTHORIZATION};
 use serde::{Deserialize, Deserializer};
 use std::env;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+mod auth;
+mod error;
+mod filter;
+mod output;
+mod template;
+mod timewarrior;
+
+use error::Error;
+use output::{FileOutput, OutputBackend, OutputKind, PdfOutput, S3Output, StdoutOutput};
 
 /// Convert timestamp (ms) to `DateTime<Utc>`
-fn parse_timestamp(ms: i64) -> DateTime<Utc> {
+fn parse_timestamp(ms: i64) -> Result<DateTime<Utc>, Error> {
     Utc.timestamp_millis_opt(ms)
         .single()
-        .expect("Invalid timestamp")
+        .ok_or_else(|| Error::Parse(format!("invalid timestamp `{ms}`")))
 }
 
 /// Custom deserializer for timestamps
@@ -17,19 +30,19 @@ where
     D: Deserializer<'de>,
 {
     let timestamp: i64 = Deserialize::deserialize(deserializer)?;
-    Ok(parse_timestamp(timestamp))
+    parse_timestamp(timestamp).map_err(serde::de::Error::custom)
 }
 
 /// Struct for project details
 #[derive(Debug, Deserialize)]
-struct Project {
-    key: String, // Project name (e.g., "BAZEL")
+pub(crate) struct Project {
+    pub(crate) key: String, // Project name (e.g., "BAZEL")
 }
 
 /// Enum to represent PR states
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-enum ReviewState {
+pub(crate) enum ReviewState {
     Opened,
     Closed,
     Deleted,
@@ -47,14 +60,14 @@ impl std::fmt::Display for ReviewState {
 
 /// Struct for the inner "review" field in the API response
 #[derive(Debug, Deserialize)]
-struct Review {
-    number: i32, // PR ID
-    title: String,
-    state: ReviewState,
-    project: Project,
+pub(crate) struct Review {
+    pub(crate) number: i32, // PR ID
+    pub(crate) title: String,
+    pub(crate) state: ReviewState,
+    pub(crate) project: Project,
 
     #[serde(deserialize_with = "deserialize_timestamp", rename = "createdAt")]
-    created_at: DateTime<Utc>,
+    pub(crate) created_at: DateTime<Utc>,
 }
 
 /// Wrapper struct matching the API response structure
@@ -63,53 +76,78 @@ struct ReviewWrapper {
     review: Review,
 }
 
-/// Root API response
+/// Root API response. `next` is Space's pagination cursor: present and
+/// non-empty when more results follow, passed back as `$skip` to fetch them.
 #[derive(Debug, Deserialize)]
 struct ApiResponse {
     data: Vec<ReviewWrapper>,
+    next: Option<String>,
 }
 
-/// Fetch PRs from JetBrains Space API.
-fn fetch_prs_for_month(
-    date: NaiveDate,
+/// Fetch every PR authored by `user_id` with a `createdAt` between
+/// `start_date` and `end_date` (inclusive), following Space's `$top`/`next`
+/// cursor pagination until the full result set has been accumulated.
+fn fetch_prs_for_range(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
     space_domain: &str,
     user_id: &str,
     project_id: &str,
     token: &str,
-) -> Result<Vec<Review>, Box<dyn std::error::Error>> {
-    let month = date.month();
-    let year = date.year();
+) -> Result<Vec<Review>, Error> {
+    let client = Client::new();
+    let mut reviews = Vec::new();
+    let mut skip: Option<String> = None;
+
+    loop {
+        let mut url = format!(
+            "https://{}/api/http/projects/id:{}/code-reviews?state=&author={}&from={}&to={}&$top=100&$fields=data(review(title,createdAt,state,number,project)),next",
+            space_domain, project_id, user_id, start_date, end_date
+        );
+        if let Some(skip) = &skip {
+            url.push_str(&format!("&$skip={skip}"));
+        }
 
-    let start_date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-    let end_date = NaiveDate::from_ymd_opt(year, month + 1, 1)
-        .unwrap_or_else(|| NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap())
-        .pred_opt()
-        .unwrap();
+        let response = client
+            .get(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .header(ACCEPT, "application/json")
+            .send()?;
 
-    // API request URL
-    let url = format!(
-        "https://{}/api/http/projects/id:{}/code-reviews?state=&author={}&from={}&to={}&$fields=data(review(title,createdAt,state,number,project))",
-        space_domain, project_id, user_id, start_date, end_date
-    );
+        let status = response.status();
+        let response_text = response.text()?; // Get raw JSON to debug
 
-    let client = Client::new();
-    let response = client
-        .get(&url)
-        .header(AUTHORIZATION, format!("Bearer {}", token))
-        .header(ACCEPT, "application/json")
-        .send()?;
+        if !status.is_success() {
+            return Err(error::decode_api_error(status.as_u16(), &response_text));
+        }
+
+        let api_response = serde_json::from_str::<ApiResponse>(&response_text)?;
+        reviews.extend(api_response.data.into_iter().map(|wrapper| wrapper.review));
 
-    let response_text = response.text()?; // Get raw JSON to debug
-    let api_response = serde_json::from_str::<ApiResponse>(&response_text)?;
+        match api_response.next.filter(|next| !next.is_empty()) {
+            Some(next) => skip = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(reviews)
+}
 
-    Ok(api_response
-        .data
-        .into_iter()
-        .map(|wrapper| wrapper.review)
-        .collect::<Vec<Review>>())
+/// Fetch every PR authored by `user_id` that was created during `date`'s
+/// calendar month.
+fn fetch_prs_for_month(
+    date: NaiveDate,
+    space_domain: &str,
+    user_id: &str,
+    project_id: &str,
+    token: &str,
+) -> Result<Vec<Review>, Error> {
+    let start_date = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
+    let end_date = last_day_of_month(date);
+    fetch_prs_for_range(start_date, end_date, space_domain, user_id, project_id, token)
 }
 
-fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+pub(crate) fn last_day_of_month(date: NaiveDate) -> NaiveDate {
     let year = date.year();
     let month = date.month();
     let next_month = if month == 12 { 1 } else { month + 1 };
@@ -121,116 +159,275 @@ fn last_day_of_month(date: NaiveDate) -> NaiveDate {
         .unwrap()
 }
 
-fn render_template(
-    prs: Vec<Review>,
-    month: NaiveDate,
-    percent_creative: i32,
-    space_domain: &str,
-    user_name: &str,
-) -> String {
-    let prs = prs
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, pr)| render_pr(idx as i32 + 1, pr, space_domain, user_name))
-        .collect::<Vec<String>>()
-        .join("\n");
-
-    let last_day_of_month = last_day_of_month(month).format("%d.%m.%Y").to_string();
-    let month = month.format("%m.%Y").to_string();
-
-    TEMPLATE
-        .replace("{prs}", &prs)
-        .replace("{month}", &month)
-        .replace("{percent_creative}", &percent_creative.to_string())
-        .replace("{last_day_of_month}", &last_day_of_month)
-}
-
-fn render_pr(idx: i32, pr: &Review, domain: &str, author: &str) -> Option<String> {
-    let project = &pr.project.key;
-    let number = pr.number;
-    let url = format!("https://{domain}/p/{project}/reviews/{number}");
-    let link = format!("[{}]({})", url, url);
-    let status = match pr.state {
-        ReviewState::Opened => "Unfinished",
-        ReviewState::Closed => "Finished",
-        ReviewState::Deleted => return None,
-    };
-    let creation_date = pr.created_at.format("%d.%m.%Y").to_string();
-    Some(format!(
-        "| {} | {} | {} | {} | {} | {} |",
-        idx,
-        pr.title.replace("|", "\\|"),
-        author,
-        link,
-        status,
-        creation_date
-    ))
+fn previous_month() -> NaiveDate {
+    let now = Utc::now();
+    NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
+        .unwrap()
+        .pred_opt()
+        .unwrap()
 }
 
-const TEMPLATE: &str = r#"
----
-title: "Formularz rejestracji czasu pracy twórczej"
-author: "JetBrains Poland sp. z o.o."
-date: "{last_day_of_month}"
-geometry: a4paper,landscape,margin=2cm
-fontsize: 10pt
-mainfont: DejaVu Serif
-lang: pl-PL
----
+/// JetBrains Space creative-time copyright form generator.
+#[derive(Debug, Parser)]
+struct Cli {
+    /// Month to generate the form for, as `YYYY-MM` (defaults to last month).
+    /// Ignored when `--from`/`--to` are given.
+    month: Option<String>,
+
+    /// First month of the range to generate forms for, as `YYYY-MM`. Must be
+    /// given together with `--to`.
+    #[arg(long)]
+    from: Option<String>,
+
+    /// Last month (inclusive) of the range to generate forms for, as
+    /// `YYYY-MM`. Must be given together with `--from`.
+    #[arg(long)]
+    to: Option<String>,
+
+    /// With `--from`/`--to`, render one document per month instead of a
+    /// single combined one.
+    #[arg(long)]
+    split: bool,
+
+    /// Path to a custom MiniJinja template (`.md.jinja`); falls back to the
+    /// built-in template when not set.
+    #[arg(long, env = "TEMPLATE_PATH")]
+    template: Option<PathBuf>,
+
+    /// Filter expression selecting which reviews land in the form, e.g.
+    /// `state = Closed AND project = BAZEL`.
+    #[arg(long, env = "SPACE_FILTER")]
+    filter: Option<String>,
+
+    /// Compute `percent_creative` from a Timewarrior JSON interval export
+    /// read on stdin, instead of reading `PERCENT_CREATIVE` from the env.
+    #[arg(long)]
+    timewarrior: bool,
+
+    /// Tags counted as creative time when `--timewarrior` is used (defaults
+    /// to `creative`).
+    #[arg(long = "creative-tag")]
+    creative_tags: Vec<String>,
+
+    /// Where to send the rendered form.
+    #[arg(long, value_enum, default_value = "stdout")]
+    output: OutputKind,
+
+    /// Destination path for `--output file` or `--output pdf`.
+    #[arg(long)]
+    output_path: Option<PathBuf>,
+}
 
-Warszawa, {last_day_of_month}
+fn main() {
+    dotenv::dotenv().ok();
 
-# Formularz rejestracji czasu pracy twórczej i Utworów w JetBrains Poland spółka z ograniczoną odpowiedzialnością / Registration form for creative time and Works at JetBrains Poland spółka z ograniczoną odpowiedzialnością
+    if let Err(err) = run() {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
 
-## Dotyczy miesiąca / Concerns the month of: {month}
+fn parse_month_arg(s: &str) -> Result<NaiveDate, Error> {
+    NaiveDate::parse_from_str(&format!("{s}-01"), "%Y-%m-%d")
+        .map_err(|_| Error::Config(format!("invalid date `{s}`, use YYYY-MM")))
+}
 
-| Lp. | Utwór / Work | Autor / Author | Forma ustalenia / Form of the Work’s establishment | Status | Data powstania / Date of creation |
-| --- | ----------------------------------------------- | ------------------- | ------------------------ | ---------------- | -------------------- |
-{prs}
+/// The first-of-month dates from `from` to `to`, inclusive.
+fn months_in_range(from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>, Error> {
+    if to < from {
+        return Err(Error::Config("--to must not be before --from".into()));
+    }
+    let mut months = Vec::new();
+    let mut current = from;
+    while current <= to {
+        months.push(current);
+        current = last_day_of_month(current).succ_opt().unwrap();
+    }
+    Ok(months)
+}
 
-### Total % of actual working time spent by creative time: {percent_creative}%
+/// Resolve the `month`/`from`/`to` CLI arguments into the list of months to
+/// generate a form for.
+fn resolve_months(cli: &Cli) -> Result<Vec<NaiveDate>, Error> {
+    match (&cli.from, &cli.to) {
+        (Some(from), Some(to)) => months_in_range(parse_month_arg(from)?, parse_month_arg(to)?),
+        (None, None) => Ok(vec![match &cli.month {
+            None => previous_month(),
+            Some(m) => parse_month_arg(m)?,
+        }]),
+        _ => Err(Error::Config(
+            "--from and --to must be given together".into(),
+        )),
+    }
+}
 
+fn apply_filter(prs: Vec<Review>, expr: &str) -> Result<Vec<Review>, Box<dyn std::error::Error>> {
+    let expr = filter::parse(expr)?;
+    let mut filtered = Vec::with_capacity(prs.len());
+    for pr in prs {
+        if filter::eval(&expr, &pr)? {
+            filtered.push(pr);
+        }
+    }
+    Ok(filtered)
+}
 
-## Oświadczenie Pracownika
+/// Insert `filename` as a stem suffix before `path`'s extension, e.g.
+/// `form.md` + `copyright-2024-03` -> `form-copyright-2024-03.md`. Used to
+/// scope an explicit `--output-path` to a single month under `--split`, the
+/// same way `S3_KEY` is scoped to a prefix.
+fn month_scoped_path(path: &Path, filename: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(filename);
+    let mut new_name = format!("{stem}-{filename}");
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        new_name.push('.');
+        new_name.push_str(ext);
+    }
+    path.with_file_name(new_name)
+}
 
-Niniejszym potwierdzam, że według mojej najlepszej wiedzy wskazany/e wyżej utwór/utwory stanowi/ą wynik mojej działalności twórczej o indywidualnym charakterze chroniony/e przepisami ustawy z dnia 4 lutego 1994 r. O prawie autorskim i prawach pokrewnych (t.j.: Dz.U. z 2022 r., poz. 2509). Ponadto oświadczam, że w miesiącu, którego dotyczy to oświadczenie mój czas pracy kreatywnej nad tworzeniem ww. Utworu/Utworów w stosunku do całego efektywnego czasu pracy (z wyłączeniem nieobecności w pracy) wyniósł {percent_creative} procent.
+fn output_backend(cli: &Cli, filename: &str) -> Result<Box<dyn OutputBackend>, Error> {
+    Ok(match cli.output {
+        OutputKind::Stdout => Box::new(StdoutOutput),
+        OutputKind::File => Box::new(FileOutput {
+            path: match &cli.output_path {
+                Some(path) if cli.split => month_scoped_path(path, filename),
+                Some(path) => path.clone(),
+                None => PathBuf::from(format!("{filename}.md")),
+            },
+        }),
+        OutputKind::Pdf => Box::new(PdfOutput {
+            path: match &cli.output_path {
+                Some(path) if cli.split => month_scoped_path(path, filename),
+                Some(path) => path.clone(),
+                None => PathBuf::from(format!("{filename}.pdf")),
+            },
+        }),
+        OutputKind::S3 => Box::new(S3Output {
+            endpoint: error::require_env("S3_ENDPOINT")?,
+            region: error::require_env("S3_REGION")?,
+            bucket: error::require_env("S3_BUCKET")?,
+            // `S3_KEY` is a prefix, not a full key: without this, every
+            // month in a `--split` run would resolve to the same object
+            // and overwrite the last one.
+            key: match env::var("S3_KEY") {
+                Ok(prefix) => format!("{prefix}/{filename}.md"),
+                Err(_) => format!("{filename}.md"),
+            },
+        }),
+    })
+}
 
-## Employee’s declaration
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let months = resolve_months(&cli)?;
+
+    let space_domain = error::require_env("SPACE_DOMAIN")?;
+    let project_id = error::require_env("SPACE_PROJECT_ID")?;
+    let token = match env::var("SPACE_TOKEN") {
+        Ok(token) => token,
+        Err(_) => {
+            let client_id = error::require_env("SPACE_CLIENT_ID")?;
+            auth::get_access_token(&space_domain, &client_id)?
+        }
+    };
+    let user_id = error::require_env("SPACE_USER_ID")?;
+    let user_name = error::require_env("USER_NAME")?;
 
-I hereby declare that, to my best knowledge, this (these) work(s) is (are) the result of my creative activity of an individual character protected by the provisions of the Act of 4 February 1994 on Copyright and Related Rights (consolidated text: Journal of Laws of 2022, item 2509). I also declare that, in the month which this declaration concerns, I have worked creatively on creating the above Work(s) for {percent_creative} percent of the entire effective working time (i.e., excluding absences at work).
-"#;
+    let creative_tags: std::collections::HashSet<String> = if cli.creative_tags.is_empty() {
+        std::iter::once("creative".to_string()).collect()
+    } else {
+        cli.creative_tags.iter().cloned().collect()
+    };
+    let timewarrior_export = if cli.timewarrior {
+        let mut raw = String::new();
+        std::io::stdin().read_to_string(&mut raw)?;
+        Some(raw)
+    } else {
+        None
+    };
 
-fn previous_month() -> NaiveDate {
-    let now = Utc::now();
-    NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
-        .unwrap()
-        .pred_opt()
-        .unwrap()
-}
+    let percent_creative_for = |start: NaiveDate, end_exclusive: NaiveDate| -> Result<i32, Box<dyn std::error::Error>> {
+        match &timewarrior_export {
+            Some(export) => {
+                timewarrior::percent_creative(export, start, end_exclusive, &creative_tags)
+            }
+            None => {
+                let raw = error::require_env("PERCENT_CREATIVE")?;
+                Ok(raw
+                    .parse::<i32>()
+                    .map_err(|_| Error::Config(format!("invalid PERCENT_CREATIVE `{raw}`")))?)
+            }
+        }
+    };
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    dotenv::dotenv().ok();
+    if cli.split {
+        for month in &months {
+            let range_end_exclusive = last_day_of_month(*month).succ_opt().unwrap();
+            let percent_creative = percent_creative_for(*month, range_end_exclusive)?;
+
+            let mut prs = fetch_prs_for_month(*month, &space_domain, &user_id, &project_id, &token)?;
+            if let Some(expr) = &cli.filter {
+                prs = apply_filter(prs, expr)?;
+            }
+
+            let rendered = template::render(
+                prs,
+                *month,
+                None,
+                percent_creative,
+                &space_domain,
+                &user_name,
+                cli.template.as_deref(),
+            )?;
+
+            let filename = format!("copyright-{}", month.format("%Y-%m"));
+            output_backend(&cli, &filename)?.write(rendered.as_bytes(), &filename)?;
+        }
+    } else {
+        let first_month = *months.first().expect("resolve_months never returns empty");
+        let last_month = *months.last().expect("resolve_months never returns empty");
+        let range_end_exclusive = last_day_of_month(last_month).succ_opt().unwrap();
+        let percent_creative = percent_creative_for(first_month, range_end_exclusive)?;
+
+        let mut prs = Vec::new();
+        for month in &months {
+            prs.extend(fetch_prs_for_month(
+                *month,
+                &space_domain,
+                &user_id,
+                &project_id,
+                &token,
+            )?);
+        }
+        if let Some(expr) = &cli.filter {
+            prs = apply_filter(prs, expr)?;
+        }
 
-    let args: Vec<String> = env::args().collect();
-    let month = args.get(1).map_or_else(previous_month, |m| {
-        NaiveDate::parse_from_str(&format!("{}-01", m), "%Y-%m-%d")
-            .expect("Invalid date format, use YYYY-MM")
-    });
-
-    let space_domain = env::var("SPACE_DOMAIN").expect("Missing SPACE_DOMAIN in env");
-    let project_id = env::var("SPACE_PROJECT_ID").expect("Missing SPACE_PROJECT_ID in env");
-    let token = env::var("SPACE_TOKEN").expect("Missing SPACE_TOKEN in env");
-    let user_id = env::var("SPACE_USER_ID").expect("Missing SPACE_USER_ID in env");
-    let user_name = env::var("USER_NAME").expect("Missing USER_NAME in env");
-    let percent_creative = env::var("PERCENT_CREATIVE")
-        .expect("Missing PERCENT_CREATIVE in env")
-        .parse::<i32>()
-        .expect("Invalid PERCENT_CREATIVE");
-
-    let prs = fetch_prs_for_month(month, &space_domain, &user_id, &project_id, &token)?;
-
-    let rendered = render_template(prs, month, percent_creative, &space_domain, &user_name);
-    println!("{}", rendered);
+        let rendered = template::render(
+            prs,
+            first_month,
+            Some(last_month),
+            percent_creative,
+            &space_domain,
+            &user_name,
+            cli.template.as_deref(),
+        )?;
+
+        let filename = if first_month == last_month {
+            format!("copyright-{}", first_month.format("%Y-%m"))
+        } else {
+            format!(
+                "copyright-{}_{}",
+                first_month.format("%Y-%m"),
+                last_month.format("%Y-%m")
+            )
+        };
+        output_backend(&cli, &filename)?.write(rendered.as_bytes(), &filename)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,110 @@
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::Command;
+
+use clap::ValueEnum;
+
+use crate::error::Error;
+
+/// Where a rendered form can be sent.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum OutputKind {
+    Stdout,
+    File,
+    Pdf,
+    S3,
+}
+
+/// A sink for a rendered form. `filename` is a backend-agnostic base name
+/// (no extension) derived from the target month, e.g. `copyright-2024-03`,
+/// or from the month range of a combined run, e.g. `copyright-2024-01_2024-06`.
+pub(crate) trait OutputBackend {
+    fn write(&self, rendered: &[u8], filename: &str) -> Result<(), Error>;
+}
+
+pub(crate) struct StdoutOutput;
+
+impl OutputBackend for StdoutOutput {
+    fn write(&self, rendered: &[u8], _filename: &str) -> Result<(), Error> {
+        std::io::stdout()
+            .write_all(rendered)
+            .map_err(|e| Error::Config(format!("could not write to stdout: {e}")))
+    }
+}
+
+pub(crate) struct FileOutput {
+    pub(crate) path: PathBuf,
+}
+
+impl OutputBackend for FileOutput {
+    fn write(&self, rendered: &[u8], _filename: &str) -> Result<(), Error> {
+        fs::write(&self.path, rendered)
+            .map_err(|e| Error::Config(format!("could not write {}: {e}", self.path.display())))
+    }
+}
+
+/// Runs the rendered Markdown (with its `geometry`/`mainfont`/`fontsize`
+/// front-matter) through pandoc to produce a signed-ready PDF.
+pub(crate) struct PdfOutput {
+    pub(crate) path: PathBuf,
+}
+
+impl OutputBackend for PdfOutput {
+    fn write(&self, rendered: &[u8], filename: &str) -> Result<(), Error> {
+        let markdown_path = std::env::temp_dir().join(format!("{filename}.md"));
+        fs::write(&markdown_path, rendered)
+            .map_err(|e| Error::Config(format!("could not write temporary markdown: {e}")))?;
+
+        let status = Command::new("pandoc")
+            .arg(&markdown_path)
+            .arg("--pdf-engine=xelatex")
+            .arg("-o")
+            .arg(&self.path)
+            .status()
+            .map_err(|e| Error::Config(format!("failed to run pandoc: {e}")))?;
+
+        if !status.success() {
+            return Err(Error::Config(format!(
+                "pandoc exited with status {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Uploads the rendered form to an S3-compatible bucket.
+pub(crate) struct S3Output {
+    pub(crate) endpoint: String,
+    pub(crate) region: String,
+    pub(crate) bucket: String,
+    pub(crate) key: String,
+}
+
+impl OutputBackend for S3Output {
+    fn write(&self, rendered: &[u8], _filename: &str) -> Result<(), Error> {
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| Error::Config(format!("could not start async runtime: {e}")))?;
+
+        runtime.block_on(async {
+            let region = aws_sdk_s3::config::Region::new(self.region.clone());
+            let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+                .region(region)
+                .endpoint_url(&self.endpoint)
+                .load()
+                .await;
+            let client = aws_sdk_s3::Client::new(&config);
+
+            client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&self.key)
+                .body(aws_sdk_s3::primitives::ByteStream::from(rendered.to_vec()))
+                .send()
+                .await
+                .map_err(|e| Error::Config(format!("S3 upload failed: {e}")))?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use minijinja::{context, Environment};
+use serde::Serialize;
+
+use crate::{last_day_of_month, Review, ReviewState};
+
+/// Embedded template used when no `--template` path is supplied.
+const DEFAULT_TEMPLATE: &str = r#"
+---
+title: "Formularz rejestracji czasu pracy twórczej"
+author: "JetBrains Poland sp. z o.o."
+date: "{{ last_day_of_month }}"
+geometry: a4paper,landscape,margin=2cm
+fontsize: 10pt
+mainfont: DejaVu Serif
+lang: pl-PL
+---
+
+Warszawa, {{ last_day_of_month }}
+
+# Formularz rejestracji czasu pracy twórczej i Utworów w JetBrains Poland spółka z ograniczoną odpowiedzialnością / Registration form for creative time and Works at JetBrains Poland spółka z ograniczoną odpowiedzialnością
+
+## Dotyczy miesiąca / Concerns the month of: {{ month }}
+
+| Lp. | Utwór / Work | Autor / Author | Forma ustalenia / Form of the Work’s establishment | Status | Data powstania / Date of creation |
+| --- | ----------------------------------------------- | ------------------- | ------------------------ | ---------------- | -------------------- |
+{%- for review in reviews %}
+| {{ review.index }} | {{ review.title|pipe_escape }} | {{ user_name }} | [{{ review.url }}]({{ review.url }}) | {{ review.status }} | {{ review.created_at|date_format("%d.%m.%Y") }} |
+{%- endfor %}
+
+### Total % of actual working time spent by creative time: {{ percent_creative }}%
+
+
+## Oświadczenie Pracownika
+
+Niniejszym potwierdzam, że według mojej najlepszej wiedzy wskazany/e wyżej utwór/utwory stanowi/ą wynik mojej działalności twórczej o indywidualnym charakterze chroniony/e przepisami ustawy z dnia 4 lutego 1994 r. O prawie autorskim i prawach pokrewnych (t.j.: Dz.U. z 2022 r., poz. 2509). Ponadto oświadczam, że w miesiącu, którego dotyczy to oświadczenie mój czas pracy kreatywnej nad tworzeniem ww. Utworu/Utworów w stosunku do całego efektywnego czasu pracy (z wyłączeniem nieobecności w pracy) wyniósł {{ percent_creative }} procent.
+
+## Employee’s declaration
+
+I hereby declare that, to my best knowledge, this (these) work(s) is (are) the result of my creative activity of an individual character protected by the provisions of the Act of 4 February 1994 on Copyright and Related Rights (consolidated text: Journal of Laws of 2022, item 2509). I also declare that, in the month which this declaration concerns, I have worked creatively on creating the above Work(s) for {{ percent_creative }} percent of the entire effective working time (i.e., excluding absences at work).
+"#;
+
+/// One row of the rendered form, exposed to the template as a member of `reviews`.
+/// `created_at` is a plain ISO (`YYYY-MM-DD`) date rather than a pre-formatted
+/// string, so custom templates can apply their own date filters, e.g. via
+/// [`date_format`].
+#[derive(Debug, Serialize)]
+pub struct ReviewContext {
+    pub index: i32,
+    pub title: String,
+    pub url: String,
+    pub status: &'static str,
+    pub created_at: NaiveDate,
+}
+
+/// Build the list of template-facing review rows, dropping `Deleted` entries
+/// the same way `render_pr` used to.
+fn build_review_contexts(prs: &[Review], space_domain: &str) -> Vec<ReviewContext> {
+    prs.iter()
+        .enumerate()
+        .filter_map(|(idx, pr)| {
+            let status = match pr.state {
+                ReviewState::Opened => "Unfinished",
+                ReviewState::Closed => "Finished",
+                ReviewState::Deleted => return None,
+            };
+            let project = &pr.project.key;
+            let number = pr.number;
+            let url = format!("https://{space_domain}/p/{project}/reviews/{number}");
+            Some(ReviewContext {
+                index: idx as i32 + 1,
+                title: pr.title.clone(),
+                url,
+                status,
+                created_at: pr.created_at.date_naive(),
+            })
+        })
+        .collect()
+}
+
+/// Escapes `|` so table cells can't break out of the Markdown table, mirroring
+/// what `render_pr` used to do with `.replace("|", "\\|")`.
+fn pipe_escape(value: String) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Formats an ISO (`YYYY-MM-DD`) date string with a `chrono` strftime
+/// pattern, letting custom templates render `created_at` however they like.
+fn date_format(value: String, fmt: String) -> Result<String, minijinja::Error> {
+    let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|e| {
+        minijinja::Error::new(
+            minijinja::ErrorKind::InvalidOperation,
+            format!("`date_format` expects an ISO date, got `{value}`: {e}"),
+        )
+    })?;
+    Ok(date.format(&fmt).to_string())
+}
+
+fn build_environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.add_filter("pipe_escape", pipe_escape);
+    env.add_filter("date_format", date_format);
+    env
+}
+
+/// Render the copyright form, loading the template from `template_path` if
+/// given and falling back to [`DEFAULT_TEMPLATE`] otherwise.
+///
+/// `month` is the first month covered by the form; when `range_end` is
+/// `Some` and differs from `month`, the declared period becomes a
+/// `MM.YYYY - MM.YYYY` span instead of a single month, to support
+/// `--from`/`--to` multi-month forms.
+pub fn render(
+    prs: Vec<Review>,
+    month: NaiveDate,
+    range_end: Option<NaiveDate>,
+    percent_creative: i32,
+    space_domain: &str,
+    user_name: &str,
+    template_path: Option<&Path>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let source = match template_path {
+        Some(path) => fs::read_to_string(path)?,
+        None => DEFAULT_TEMPLATE.to_string(),
+    };
+
+    let mut env = build_environment();
+    env.add_template("form", &source)?;
+    let tmpl = env.get_template("form")?;
+
+    let last_month = range_end.unwrap_or(month);
+    let month_label = if last_month != month {
+        format!(
+            "{} - {}",
+            month.format("%m.%Y"),
+            last_month.format("%m.%Y")
+        )
+    } else {
+        month.format("%m.%Y").to_string()
+    };
+
+    let reviews = build_review_contexts(&prs, space_domain);
+    let rendered = tmpl.render(context! {
+        month => month_label,
+        last_day_of_month => last_day_of_month(last_month).format("%d.%m.%Y").to_string(),
+        percent_creative => percent_creative,
+        user_name => user_name,
+        reviews => reviews,
+    })?;
+
+    Ok(rendered)
+}
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+
+/// A single Timewarrior tracked interval, as exported by `timew export`.
+#[derive(Debug, Deserialize)]
+struct Interval {
+    #[allow(dead_code)]
+    id: i64,
+    #[serde(deserialize_with = "deserialize_timew_stamp")]
+    start: DateTime<Utc>,
+    #[serde(default, deserialize_with = "deserialize_timew_stamp_opt")]
+    end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn deserialize_timew_stamp<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: String = Deserialize::deserialize(deserializer)?;
+    parse_timew_stamp(&raw).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_timew_stamp_opt<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: Option<String> = Deserialize::deserialize(deserializer)?;
+    raw.map(|raw| parse_timew_stamp(&raw).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn parse_timew_stamp(raw: &str) -> Result<DateTime<Utc>, String> {
+    NaiveDateTime::parse_from_str(raw, "%Y%m%dT%H%M%SZ")
+        .map(|naive| naive.and_utc())
+        .map_err(|e| format!("invalid Timewarrior timestamp `{raw}`: {e}"))
+}
+
+/// Compute the percentage of tracked time within
+/// `[range_start, range_end_exclusive)` whose tags intersect
+/// `creative_tags`, from an already-read Timewarrior JSON interval export.
+/// Passing a single month's boundaries (first day of the month and the first
+/// day of the next one) reproduces the original one-month behaviour; a wider
+/// range covers `--from`/`--to`. Taking the export as `&str` rather than a
+/// reader lets callers read stdin once and reuse it across several ranges
+/// (e.g. one call per month with `--split`).
+pub(crate) fn percent_creative(
+    export: &str,
+    range_start: NaiveDate,
+    range_end_exclusive: NaiveDate,
+    creative_tags: &HashSet<String>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let intervals: Vec<Interval> = serde_json::from_str(export)?;
+
+    let range_start = Utc.from_utc_datetime(&range_start.and_hms_opt(0, 0, 0).unwrap());
+    let range_end = Utc.from_utc_datetime(&range_end_exclusive.and_hms_opt(0, 0, 0).unwrap());
+    let now = Utc::now();
+
+    let mut creative = chrono::Duration::zero();
+    let mut total = chrono::Duration::zero();
+
+    for interval in intervals {
+        let end = interval.end.unwrap_or(now);
+        if end <= range_start || interval.start >= range_end {
+            continue; // entirely outside the range
+        }
+        let clamped_start = interval.start.max(range_start);
+        let clamped_end = end.min(range_end);
+        if clamped_end <= clamped_start {
+            continue;
+        }
+        let duration = clamped_end - clamped_start;
+        total += duration;
+        if interval.tags.iter().any(|tag| creative_tags.contains(tag)) {
+            creative += duration;
+        }
+    }
+
+    if total.is_zero() {
+        return Ok(0);
+    }
+
+    let percent = (creative.num_seconds() as f64 / total.num_seconds() as f64) * 100.0;
+    Ok(percent.round() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> HashSet<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    fn range(start: &str, end: &str) -> (NaiveDate, NaiveDate) {
+        (
+            NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap(),
+            NaiveDate::parse_from_str(end, "%Y-%m-%d").unwrap(),
+        )
+    }
+
+    #[test]
+    fn empty_export_yields_zero_percent() {
+        let (start, end) = range("2024-03-01", "2024-04-01");
+        let percent = percent_creative("[]", start, end, &tags(&["creative"])).unwrap();
+        assert_eq!(percent, 0);
+    }
+
+    #[test]
+    fn full_overlap_splits_by_tag() {
+        let export = r#"[
+            {"id": 1, "start": "20240301T000000Z", "end": "20240301T060000Z", "tags": ["creative"]},
+            {"id": 2, "start": "20240301T060000Z", "end": "20240301T120000Z", "tags": ["admin"]}
+        ]"#;
+        let (start, end) = range("2024-03-01", "2024-04-01");
+        let percent = percent_creative(export, start, end, &tags(&["creative"])).unwrap();
+        assert_eq!(percent, 50);
+    }
+
+    #[test]
+    fn intervals_outside_the_range_are_clamped_out() {
+        let export = r#"[
+            {"id": 1, "start": "20240201T000000Z", "end": "20240201T120000Z", "tags": ["creative"]},
+            {"id": 2, "start": "20240301T000000Z", "end": "20240301T120000Z", "tags": ["creative"]}
+        ]"#;
+        let (start, end) = range("2024-03-01", "2024-04-01");
+        let percent = percent_creative(export, start, end, &tags(&["creative"])).unwrap();
+        assert_eq!(percent, 100);
+    }
+
+    #[test]
+    fn interval_spanning_the_boundary_is_clamped_to_it() {
+        let export = r#"[
+            {"id": 1, "start": "20240229T120000Z", "end": "20240301T120000Z", "tags": ["admin"]},
+            {"id": 2, "start": "20240301T120000Z", "end": "20240302T000000Z", "tags": ["creative"]}
+        ]"#;
+        let (start, end) = range("2024-03-01", "2024-04-01");
+        let percent = percent_creative(export, start, end, &tags(&["creative"])).unwrap();
+        // Only the 12h of the admin interval inside March count: 12h creative
+        // out of 24h total. Without clamping it would be 12h out of 36h (33%).
+        assert_eq!(percent, 50);
+    }
+
+    #[test]
+    fn open_interval_is_clamped_against_now() {
+        let export = r#"[{"id": 1, "start": "20240301T000000Z", "tags": ["creative"]}]"#;
+        let (start, end) = range("2024-03-01", "2024-04-01");
+        let percent = percent_creative(export, start, end, &tags(&["creative"])).unwrap();
+        assert_eq!(percent, 100);
+    }
+
+    #[test]
+    fn rejects_a_malformed_timestamp() {
+        let export = r#"[{"id": 1, "start": "not-a-timestamp", "tags": []}]"#;
+        let (start, end) = range("2024-03-01", "2024-04-01");
+        assert!(percent_creative(export, start, end, &tags(&[])).is_err());
+    }
+}